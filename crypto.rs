@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::Serialize;
+
+use crate::DrugBatch;
+
+/// Outcome of checking a batch's signature against the manufacturer's
+/// registered public key. Kept as an explicit enum rather than a bool so
+/// callers can tell "nobody vouched for this manufacturer" apart from
+/// "someone vouched, but the signature doesn't check out".
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyStatus {
+    Verified,
+    UnknownManufacturer,
+    BadSignature,
+}
+
+/// The fields a manufacturer signs over, serialized canonically. Excludes
+/// `signature` itself, since a signature can't cover its own bytes.
+fn signable_bytes(batch: &DrugBatch) -> Vec<u8> {
+    #[derive(Serialize)]
+    struct Signable<'a> {
+        id: &'a uuid::Uuid,
+        name: &'a str,
+        manufacturer: &'a str,
+        batch_number: &'a str,
+        expiry_date: &'a chrono::NaiveDate,
+    }
+    serde_json::to_vec(&Signable {
+        id: &batch.id,
+        name: &batch.name,
+        manufacturer: &batch.manufacturer,
+        batch_number: &batch.batch_number,
+        expiry_date: &batch.expiry_date,
+    })
+    .expect("batch fields must serialize")
+}
+
+/// Signs a batch's canonical fields with the manufacturer's secret key.
+pub fn sign_batch(signing_key: &SigningKey, batch: &DrugBatch) -> Vec<u8> {
+    signing_key.sign(&signable_bytes(batch)).to_vec()
+}
+
+/// Checks a batch's `signature` against the registered key for its
+/// claimed manufacturer.
+pub fn verify_batch(
+    manufacturer_keys: &HashMap<String, VerifyingKey>,
+    batch: &DrugBatch,
+) -> VerifyStatus {
+    let Some(key) = manufacturer_keys.get(&batch.manufacturer) else {
+        return VerifyStatus::UnknownManufacturer;
+    };
+    let Some(sig_bytes) = &batch.signature else {
+        return VerifyStatus::BadSignature;
+    };
+    let Ok(signature) = Signature::from_slice(sig_bytes) else {
+        return VerifyStatus::BadSignature;
+    };
+    match key.verify(&signable_bytes(batch), &signature) {
+        Ok(()) => VerifyStatus::Verified,
+        Err(_) => VerifyStatus::BadSignature,
+    }
+}
+
+/// Key files are PKCS#8 (secret) / SPKI (public) PEM, per the TUF-style
+/// key-management model this subsystem borrows from.
+pub fn load_signing_key(path: &str) -> SigningKey {
+    let pem = std::fs::read_to_string(path).expect("failed to read secret key file");
+    SigningKey::from_pkcs8_pem(&pem).expect("secret key file is not valid PKCS#8 PEM")
+}
+
+pub fn load_verifying_key(path: &str) -> VerifyingKey {
+    let pem = std::fs::read_to_string(path).expect("failed to read public key file");
+    VerifyingKey::from_public_key_pem(&pem).expect("public key file is not valid SPKI PEM")
+}
+
+/// Generates a fresh manufacturer/regulator keypair and writes it as
+/// `<prefix>.sk.pem` (secret, PKCS#8) and `<prefix>.pk.pem` (public, SPKI).
+pub fn generate_keypair(prefix: &str) {
+    let signing_key = SigningKey::generate(&mut rand_core::OsRng);
+    let verifying_key = signing_key.verifying_key();
+    let secret_pem = signing_key.to_pkcs8_pem(LineEnding::LF).expect("secret key must encode to PKCS#8 PEM");
+    let public_pem = verifying_key.to_public_key_pem(LineEnding::LF).expect("public key must encode to SPKI PEM");
+    std::fs::write(format!("{prefix}.sk.pem"), secret_pem.as_bytes())
+        .expect("failed to write secret key file");
+    std::fs::write(format!("{prefix}.pk.pem"), public_pem)
+        .expect("failed to write public key file");
+}
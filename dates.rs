@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+
+/// How to interpret a raw expiry-date string supplied at mint/import time.
+/// Modeled as a small type-coercion enum so new input formats are a new
+/// variant rather than a pile of ad-hoc parsing flags.
+#[derive(Debug, Clone)]
+pub enum DateConversion {
+    /// An RFC3339 timestamp, e.g. "2027-01-31T00:00:00Z".
+    Timestamp,
+    /// A strftime-style pattern, e.g. "%Y-%m-%d" or "%d/%m/%Y".
+    TimestampFmt(String),
+}
+
+impl Default for DateConversion {
+    fn default() -> Self {
+        DateConversion::TimestampFmt("%Y-%m-%d".to_string())
+    }
+}
+
+impl DateConversion {
+    /// Parses `raw` into a calendar date, or a human-readable error if it
+    /// doesn't match the expected format.
+    pub fn parse(&self, raw: &str) -> Result<NaiveDate, String> {
+        match self {
+            DateConversion::Timestamp => chrono::DateTime::parse_from_rfc3339(raw)
+                .map(|dt| dt.naive_utc().date())
+                .map_err(|e| format!("'{raw}' is not a valid RFC3339 timestamp: {e}")),
+            DateConversion::TimestampFmt(fmt) => NaiveDate::parse_from_str(raw, fmt)
+                .map_err(|e| format!("'{raw}' does not match date format '{fmt}': {e}")),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+/// Locales shipped with the binary. English is the mandatory fallback; add
+/// an entry here (and a matching `locales/<tag>.ftl`) to ship a new one.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("locales/en.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+];
+
+const FALLBACK_LOCALE: &str = "en";
+
+thread_local! {
+    static CURRENT_LOCALE: RefCell<String> = RefCell::new(FALLBACK_LOCALE.to_string());
+    static BUNDLES: RefCell<HashMap<String, FluentBundle<FluentResource>>> = RefCell::new(HashMap::new());
+}
+
+fn build_bundle(locale: &str) -> FluentBundle<FluentResource> {
+    let source = CATALOGS
+        .iter()
+        .find(|(tag, _)| *tag == locale)
+        .map(|(_, src)| *src)
+        .unwrap_or_else(|| CATALOGS.iter().find(|(tag, _)| *tag == FALLBACK_LOCALE).unwrap().1);
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(res, errors)| {
+        eprintln!("warning: malformed .ftl catalog for locale '{locale}': {errors:?}");
+        res
+    });
+    let lang_id: LanguageIdentifier = locale.parse().unwrap_or_else(|_| FALLBACK_LOCALE.parse().unwrap());
+    let mut bundle = FluentBundle::new(vec![lang_id]);
+    // CLI/terminal output isn't bidi-sensitive; without this, every
+    // interpolated argument gets wrapped in invisible U+2068/U+2069 marks,
+    // which breaks copy-pasting or scripting against IDs and paths.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("locale catalog must not define duplicate message ids");
+    bundle
+}
+
+/// Sets the active locale for the current thread. Unrecognized locales
+/// silently fall back to English rather than erroring.
+pub fn set_locale(locale: &str) {
+    CURRENT_LOCALE.with(|cell| *cell.borrow_mut() = locale.to_string());
+}
+
+fn lookup(locale: &str, id: &str, args: &[(&str, &str)]) -> Option<String> {
+    BUNDLES.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let bundle = cache.entry(locale.to_string()).or_insert_with(|| build_bundle(locale));
+        let message = bundle.get_message(id)?;
+        let pattern = message.value()?;
+        let mut fluent_args = FluentArgs::new();
+        for (key, value) in args {
+            fluent_args.set(*key, FluentValue::from(*value));
+        }
+        let mut errors = Vec::new();
+        Some(bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned())
+    })
+}
+
+/// Looks up `id` in the active locale's catalog, falling back to English
+/// when the locale or the specific message is missing, and to the raw,
+/// bracketed id if even English doesn't have it.
+pub fn t(id: &str, args: &[(&str, &str)]) -> String {
+    let locale = CURRENT_LOCALE.with(|cell| cell.borrow().clone());
+    if let Some(message) = lookup(&locale, id, args) {
+        return message;
+    }
+    if locale != FALLBACK_LOCALE {
+        if let Some(message) = lookup(FALLBACK_LOCALE, id, args) {
+            return message;
+        }
+    }
+    format!("[{id}]")
+}
+
+/// Looks up a localized message by id, optionally with `key => value`
+/// interpolation arguments, falling back to English and then to the raw
+/// message id if a translation is missing.
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::t($id, &[])
+    };
+    ($id:expr, $($key:expr => $value:expr),+ $(,)?) => {
+        $crate::i18n::t($id, &[$(($key, &$value.to_string())),+])
+    };
+}
@@ -0,0 +1,156 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dates::DateConversion;
+use crate::PharmaChain;
+
+#[derive(Debug, Deserialize)]
+struct ImportRow {
+    name: String,
+    manufacturer: String,
+    batch_number: String,
+    expiry_date: String,
+}
+
+const REQUIRED_COLUMNS: [&str; 4] = ["name", "manufacturer", "batch_number", "expiry_date"];
+
+/// Result of a bulk import: how many batches were minted, and which input
+/// records were skipped along with why. Positions are 1-indexed: for CSV
+/// the line number counting the header row, for JSON the element's
+/// position in the top-level array.
+#[derive(Debug)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: Vec<(usize, String)>,
+}
+
+pub fn import(
+    chain: &mut PharmaChain,
+    path: &str,
+    date_format: &DateConversion,
+    signing_key: &SigningKey,
+) -> ImportReport {
+    let rows = match extension(path) {
+        "csv" => import_csv(path),
+        "json" => import_json(path),
+        other => panic!("unsupported import format '.{other}' (expected .csv or .json)"),
+    };
+
+    let mut report = ImportReport { imported: 0, skipped: Vec::new() };
+    for (line, row) in rows {
+        match row {
+            Ok(row) => {
+                match chain.mint_batch_no_save(&row.name, &row.manufacturer, &row.batch_number, &row.expiry_date, date_format, signing_key) {
+                    Ok(_) => report.imported += 1,
+                    Err(reason) => report.skipped.push((line, reason)),
+                }
+            }
+            Err(reason) => report.skipped.push((line, reason)),
+        }
+    }
+    // Append every row to the ledger and persist once, rather than
+    // re-serializing the whole registry after each individual mint.
+    if report.imported > 0 {
+        chain.save();
+    }
+    report
+}
+
+fn import_csv(path: &str) -> Vec<(usize, Result<ImportRow, String>)> {
+    let mut reader = csv::Reader::from_path(path).expect("failed to open CSV file");
+    let headers = reader.headers().expect("failed to read CSV headers").clone();
+    for col in REQUIRED_COLUMNS {
+        if !headers.iter().any(|h| h == col) {
+            panic!("CSV is missing required column '{col}'");
+        }
+    }
+    reader
+        .deserialize::<ImportRow>()
+        .enumerate()
+        .map(|(i, result)| {
+            let line = i + 2; // account for the header row
+            (line, result.map_err(|e| e.to_string()))
+        })
+        .collect()
+}
+
+fn import_json(path: &str) -> Vec<(usize, Result<ImportRow, String>)> {
+    let file = File::open(path).expect("failed to open JSON file");
+    let values: Vec<serde_json::Value> = serde_json::from_reader(BufReader::new(file))
+        .expect("JSON file must contain a top-level array of batch objects");
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let line = i + 1;
+            let row = serde_json::from_value::<ImportRow>(value).map_err(|e| e.to_string());
+            (line, row)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFilter {
+    All,
+    Recalled,
+    Active,
+}
+
+/// Flattened, portable view of a batch used for export so the wire
+/// format doesn't depend on `DrugBatch`'s in-memory representation (e.g.
+/// `signature` becomes hex instead of a raw byte array).
+#[derive(Debug, Serialize)]
+struct ExportRow {
+    id: Uuid,
+    name: String,
+    manufacturer: String,
+    batch_number: String,
+    expiry_date: String,
+    recalled: bool,
+    signature_hex: String,
+}
+
+pub fn export(chain: &PharmaChain, path: &str, filter: ExportFilter) {
+    let rows: Vec<ExportRow> = chain
+        .batches
+        .values()
+        .filter(|b| match filter {
+            ExportFilter::All => true,
+            ExportFilter::Recalled => b.recalled,
+            ExportFilter::Active => !b.recalled,
+        })
+        .map(|b| ExportRow {
+            id: b.id,
+            name: b.name.clone(),
+            manufacturer: b.manufacturer.clone(),
+            batch_number: b.batch_number.clone(),
+            expiry_date: b.expiry_date.to_string(),
+            recalled: b.recalled,
+            signature_hex: b.signature.as_ref().map(hex::encode).unwrap_or_default(),
+        })
+        .collect();
+
+    match extension(path) {
+        "csv" => {
+            let mut writer = csv::Writer::from_path(path).expect("failed to create CSV file");
+            for row in &rows {
+                writer.serialize(row).expect("failed to write CSV row");
+            }
+            writer.flush().expect("failed to flush CSV file");
+        }
+        "json" => {
+            let file = File::create(path).expect("failed to create JSON file");
+            serde_json::to_writer_pretty(file, &rows).expect("failed to write JSON file");
+        }
+        other => panic!("unsupported export format '.{other}' (expected .csv or .json)"),
+    }
+}
+
+fn extension(path: &str) -> &str {
+    Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("")
+}
@@ -1,12 +1,31 @@
+mod crypto;
+mod dates;
+mod i18n;
+mod importexport;
+mod ledger;
+mod recall;
+mod server;
+mod stats;
+
 use std::collections::{HashMap, HashSet};
 use std::fs::{File, OpenOptions};
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
 use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::NaiveDate;
+use ed25519_dalek::VerifyingKey;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use qrcode::QrCode;
 use image::Luma;
+use dates::DateConversion;
+use ledger::{LedgerEntry, LedgerPayload};
+use recall::{RecallManifest, RecallManifestError, SignedRecallManifest};
+
+/// How long a freshly-signed recall manifest is valid for before a node
+/// should refuse to trust it, absent a renewed signature.
+const RECALL_MANIFEST_LIFETIME_SECS: i64 = 365 * 24 * 60 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DrugBatch {
@@ -14,14 +33,28 @@ struct DrugBatch {
     name: String,
     manufacturer: String,
     batch_number: String,
-    expiry_date: String,
+    expiry_date: NaiveDate,
     recalled: bool,
+    #[serde(default)]
+    signature: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct PharmaChain {
     batches: HashMap<Uuid, DrugBatch>,
     recalls: HashSet<Uuid>,
+    events: Vec<LedgerEntry>,
+    manufacturer_keys: HashMap<String, VerifyingKey>,
+    regulator_key: Option<VerifyingKey>,
+    recall_version: u64,
+    recall_expires: i64,
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before epoch")
+        .as_secs() as i64
 }
 
 impl PharmaChain {
@@ -39,6 +72,11 @@ impl PharmaChain {
         PharmaChain {
             batches: HashMap::new(),
             recalls: HashSet::new(),
+            events: Vec::new(),
+            manufacturer_keys: HashMap::new(),
+            regulator_key: None,
+            recall_version: 0,
+            recall_expires: 0,
         }
     }
 
@@ -53,30 +91,94 @@ impl PharmaChain {
         serde_json::to_writer_pretty(writer, &self).expect("Failed to write data");
     }
 
-    fn mint_batch(&mut self, name: &str, manufacturer: &str, batch_number: &str, expiry_date: &str) -> Uuid {
+    fn mint_batch(
+        &mut self,
+        name: &str,
+        manufacturer: &str,
+        batch_number: &str,
+        expiry_date: &str,
+        date_format: &DateConversion,
+        signing_key: &ed25519_dalek::SigningKey,
+    ) -> Result<Uuid, String> {
+        let id = self.mint_batch_no_save(name, manufacturer, batch_number, expiry_date, date_format, signing_key)?;
+        self.save();
+        Ok(id)
+    }
+
+    /// Same as `mint_batch`, but leaves persisting the registry to the
+    /// caller — lets bulk callers like `importexport::import` mint many
+    /// batches and save once instead of re-serializing the whole registry
+    /// after every row.
+    fn mint_batch_no_save(
+        &mut self,
+        name: &str,
+        manufacturer: &str,
+        batch_number: &str,
+        expiry_date: &str,
+        date_format: &DateConversion,
+        signing_key: &ed25519_dalek::SigningKey,
+    ) -> Result<Uuid, String> {
+        let expiry_date = date_format.parse(expiry_date)?;
         let id = Uuid::new_v4();
-        let batch = DrugBatch {
+        let mut batch = DrugBatch {
             id,
             name: name.to_string(),
             manufacturer: manufacturer.to_string(),
             batch_number: batch_number.to_string(),
-            expiry_date: expiry_date.to_string(),
+            expiry_date,
             recalled: false,
+            signature: None,
         };
+        batch.signature = Some(crypto::sign_batch(signing_key, &batch));
         self.batches.insert(id, batch.clone());
-        self.save();
+        let entry = ledger::next_entry(&self.events, now_unix(), LedgerPayload::Mint { batch: batch.clone() });
+        self.events.push(entry);
         self.generate_qr_code(&batch);
-        id
+        Ok(id)
+    }
+
+    /// Returns the batch along with whether its signature checks out
+    /// against its claimed manufacturer's registered public key.
+    fn verify_batch(&self, id: &Uuid) -> Option<(&DrugBatch, crypto::VerifyStatus)> {
+        self.batches
+            .get(id)
+            .map(|batch| (batch, crypto::verify_batch(&self.manufacturer_keys, batch)))
     }
 
-    fn verify_batch(&self, id: &Uuid) -> Option<&DrugBatch> {
-        self.batches.get(id)
+    fn register_manufacturer_key(&mut self, manufacturer: &str, key: VerifyingKey) {
+        self.manufacturer_keys.insert(manufacturer.to_string(), key);
+        self.save();
+    }
+
+    fn register_regulator_key(&mut self, key: VerifyingKey) {
+        self.regulator_key = Some(key);
+        self.save();
     }
 
-    fn recall_batch(&mut self, id: &Uuid) -> bool {
-        if let Some(batch) = self.batches.get_mut(id) {
-            batch.recalled = true;
+    /// Recalls a batch locally and publishes a freshly-signed recall
+    /// manifest covering the whole current recall set, bumping
+    /// `recall_version` so stale copies can be detected as rollbacks.
+    fn recall_batch(&mut self, id: &Uuid, regulator_key: &ed25519_dalek::SigningKey) -> bool {
+        if self.batches.contains_key(id) {
+            self.batches.get_mut(id).unwrap().recalled = true;
             self.recalls.insert(*id);
+            let entry = ledger::next_entry(&self.events, now_unix(), LedgerPayload::Recall { id: *id });
+            self.events.push(entry);
+
+            self.recall_version += 1;
+            self.recall_expires = now_unix() + RECALL_MANIFEST_LIFETIME_SECS;
+            let manifest = RecallManifest {
+                recalled: self.recalls.iter().cloned().collect(),
+                version: self.recall_version,
+                expires: self.recall_expires,
+            };
+            let signed = SignedRecallManifest::sign(manifest, regulator_key);
+            std::fs::write(
+                "recall_manifest.json",
+                serde_json::to_vec_pretty(&signed).expect("manifest must serialize"),
+            )
+            .expect("failed to write recall manifest");
+
             self.save();
             true
         } else {
@@ -84,14 +186,63 @@ impl PharmaChain {
         }
     }
 
+    /// Ingests a recall manifest published by the regulator, refusing to
+    /// trust it if the signature doesn't check out, if it would roll the
+    /// recall list back to an older version, or if it has expired.
+    fn load_recall_manifest(
+        &mut self,
+        signed: SignedRecallManifest,
+    ) -> Result<(), RecallManifestError> {
+        signed.verify(self.regulator_key.as_ref(), self.recall_version, now_unix())?;
+        self.recalls = signed.manifest.recalled.into_iter().collect();
+        self.recall_version = signed.manifest.version;
+        self.recall_expires = signed.manifest.expires;
+        // Keep each batch's `recalled` flag in lockstep with the authoritative
+        // `recalls` set, since callers like `stats`/`export`/`verify` read the
+        // per-batch flag directly rather than consulting the set.
+        for (id, batch) in self.batches.iter_mut() {
+            batch.recalled = self.recalls.contains(id);
+        }
+        self.save();
+        Ok(())
+    }
+
+    /// Whether `id` is currently recalled, per the last recall snapshot this
+    /// node accepted. `load_recall_manifest` already refuses snapshots that
+    /// roll the version backwards or that arrive already expired; this is
+    /// the other half of that guarantee, for snapshots that were valid when
+    /// ingested but have since aged past their `expires` timestamp — once
+    /// that happens we stop trusting the snapshot rather than treating a
+    /// frozen recall list as permanently authoritative.
     fn is_recalled(&self, id: &Uuid) -> bool {
-        self.recalls.contains(id)
+        self.recalls.contains(id) && now_unix() <= self.recall_expires
+    }
+
+    /// Lists all batches whose expiry date is before `as_of`.
+    fn expired(&self, as_of: NaiveDate) -> Vec<&DrugBatch> {
+        self.batches.values().filter(|b| b.expiry_date < as_of).collect()
+    }
+
+    /// Walks the event log and confirms every hash and prev-hash link is
+    /// intact. Returns the index of the first broken link, if any.
+    fn verify_chain(&self) -> Result<(), u64> {
+        ledger::verify_chain(&self.events)
     }
 
     fn generate_qr_code(&self, batch: &DrugBatch) {
+        let signature_hex = batch
+            .signature
+            .as_ref()
+            .map(hex::encode)
+            .unwrap_or_default();
         let data = format!(
-            "Batch ID: {}\nName: {}\nManufacturer: {}\nBatch No: {}\nExpiry: {}",
-            batch.id, batch.name, batch.manufacturer, batch.batch_number, batch.expiry_date
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}",
+            t!("qr-label-batch-id"), batch.id,
+            t!("qr-label-name"), batch.name,
+            t!("qr-label-manufacturer"), batch.manufacturer,
+            t!("qr-label-batch-no"), batch.batch_number,
+            t!("qr-label-expiry"), batch.expiry_date,
+            t!("qr-label-signature"), signature_hex
         );
         let code = QrCode::new(data.as_bytes()).unwrap();
         let image = code.render::<Luma<u8>>().build();
@@ -100,48 +251,221 @@ impl PharmaChain {
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let lang = extract_lang_flag(&mut args).or_else(|| env::var("LANG").ok());
+    if let Some(lang) = lang {
+        let tag = lang.split(['.', '_']).next().unwrap_or(&lang).to_string();
+        i18n::set_locale(&tag);
+    }
+
     let mut chain = PharmaChain::new();
 
     if args.len() < 2 {
-        println!("Usage: pharmachain [mint|verify|recall] [params...]");
+        println!("{}", t!("usage-main"));
         return;
     }
 
     match args[1].as_str() {
         "mint" => {
-            if args.len() != 6 {
-                println!("Usage: pharmachain mint <name> <manufacturer> <batch_number> <expiry_date>");
+            if args.len() != 7 && args.len() != 9 {
+                println!("{}", t!("usage-mint"));
                 return;
             }
-            let id = chain.mint_batch(&args[2], &args[3], &args[4], &args[5]);
-            println!("✅ Minted batch with ID: {}\n📦 QR saved to: qr_{}.png", id, id);
+            let date_format = parse_date_format_flag(&args);
+            let signing_key = crypto::load_signing_key(&args[6]);
+            match chain.mint_batch(&args[2], &args[3], &args[4], &args[5], &date_format, &signing_key) {
+                Ok(id) => println!("{}", t!("mint-success", "id" => id, "qr_path" => format!("qr_{id}.png"))),
+                Err(reason) => println!("{}", t!("mint-invalid-date", "reason" => reason)),
+            }
         },
         "verify" => {
             if args.len() != 3 {
-                println!("Usage: pharmachain verify <uuid>");
+                println!("{}", t!("usage-verify"));
                 return;
             }
             let id = Uuid::parse_str(&args[2]).expect("Invalid UUID");
             match chain.verify_batch(&id) {
-                Some(batch) => println!("🔍 Batch Info:\n{:#?}", batch),
-                None => println!("❌ Batch not found."),
+                Some((batch, status)) => {
+                    println!("{}\n{:#?}", t!("batch-info-header"), batch);
+                    match status {
+                        crypto::VerifyStatus::Verified => println!("{}", t!("verify-verified")),
+                        crypto::VerifyStatus::UnknownManufacturer => println!("{}", t!("verify-unknown-manufacturer")),
+                        crypto::VerifyStatus::BadSignature => println!("{}", t!("verify-bad-signature")),
+                    }
+                },
+                None => println!("{}", t!("batch-not-found")),
             }
         },
         "recall" => {
-            if args.len() != 3 {
-                println!("Usage: pharmachain recall <uuid>");
+            if args.len() != 4 {
+                println!("{}", t!("usage-recall"));
                 return;
             }
             let id = Uuid::parse_str(&args[2]).expect("Invalid UUID");
-            if chain.recall_batch(&id) {
-                println!("⚠️ Batch {} has been recalled.", id);
+            let regulator_key = crypto::load_signing_key(&args[3]);
+            if chain.recall_batch(&id, &regulator_key) {
+                println!("{}", t!("recall-success", "id" => id));
             } else {
-                println!("❌ Batch not found.");
+                println!("{}", t!("batch-not-found"));
+            }
+        },
+        "verify-chain" => {
+            match chain.verify_chain() {
+                Ok(()) => println!("{}", t!("chain-intact", "count" => chain.events.len())),
+                Err(index) => println!("{}", t!("chain-broken", "index" => index)),
+            }
+        },
+        "register-key" => {
+            if args.len() != 4 {
+                println!("{}", t!("usage-register-key"));
+                return;
+            }
+            let key = crypto::load_verifying_key(&args[3]);
+            chain.register_manufacturer_key(&args[2], key);
+            println!("{}", t!("register-key-success", "manufacturer" => args[2]));
+        },
+        "register-regulator-key" => {
+            if args.len() != 3 {
+                println!("{}", t!("usage-register-regulator-key"));
+                return;
+            }
+            let key = crypto::load_verifying_key(&args[2]);
+            chain.register_regulator_key(key);
+            println!("{}", t!("register-regulator-key-success"));
+        },
+        "load-recall-manifest" => {
+            if args.len() != 3 {
+                println!("{}", t!("usage-load-recall-manifest"));
+                return;
+            }
+            let file = File::open(&args[2]).expect("failed to open manifest file");
+            let signed: SignedRecallManifest =
+                serde_json::from_reader(BufReader::new(file)).expect("invalid manifest file");
+            match chain.load_recall_manifest(signed) {
+                Ok(()) => println!("{}", t!("manifest-accepted", "version" => chain.recall_version)),
+                Err(RecallManifestError::NoRegulatorKey) => println!("{}", t!("manifest-no-regulator-key")),
+                Err(RecallManifestError::BadSignature) => println!("{}", t!("manifest-bad-signature")),
+                Err(RecallManifestError::RolledBack) => println!("{}", t!("manifest-rolled-back")),
+                Err(RecallManifestError::Expired) => println!("{}", t!("manifest-expired")),
+            }
+        },
+        "keygen" => {
+            if args.len() != 3 {
+                println!("{}", t!("usage-keygen"));
+                return;
+            }
+            crypto::generate_keypair(&args[2]);
+            println!("{}", t!("keygen-success", "prefix" => args[2]));
+        },
+        "serve" => {
+            let port: u16 = args.get(2).map(|p| p.parse().expect("invalid port")).unwrap_or(8080);
+            server::serve(chain, port);
+        },
+        "import" => {
+            if args.len() != 4 && args.len() != 6 {
+                println!("{}", t!("usage-import"));
+                return;
+            }
+            let date_format = parse_date_format_flag(&args);
+            let signing_key = crypto::load_signing_key(&args[3]);
+            let report = importexport::import(&mut chain, &args[2], &date_format, &signing_key);
+            println!("{}", t!("import-success", "count" => report.imported));
+            for (line, reason) in &report.skipped {
+                println!("{}", t!("import-skipped", "line" => line, "reason" => reason));
+            }
+        },
+        "export" => {
+            if args.len() < 3 {
+                println!("{}", t!("usage-export"));
+                return;
+            }
+            let filter = match args.get(3).map(String::as_str) {
+                None => importexport::ExportFilter::All,
+                Some("--recalled") => importexport::ExportFilter::Recalled,
+                Some("--active") => importexport::ExportFilter::Active,
+                Some(other) => {
+                    println!("{}", t!("unknown-filter", "filter" => other));
+                    return;
+                }
+            };
+            importexport::export(&chain, &args[2], filter);
+            println!("{}", t!("export-success", "path" => args[2]));
+        },
+        "expired" => {
+            let as_of = match args.get(2).map(String::as_str) {
+                Some("--as-of") => {
+                    let raw = args.get(3).expect("--as-of requires a date argument");
+                    NaiveDate::parse_from_str(raw, "%Y-%m-%d").expect("invalid --as-of date, expected YYYY-MM-DD")
+                }
+                Some(other) => {
+                    println!("{}", t!("unknown-option", "option" => other));
+                    return;
+                }
+                None => now_unix_date(),
+            };
+            let mut expired = chain.expired(as_of);
+            expired.sort_by_key(|b| b.expiry_date);
+            if expired.is_empty() {
+                println!("{}", t!("expired-none", "date" => as_of));
+            } else {
+                for batch in expired {
+                    println!("{}", t!("expired-entry", "name" => batch.name, "id" => batch.id, "date" => batch.expiry_date));
+                }
+            }
+        },
+        "stats" => {
+            let stats = stats::compute(&chain, now_unix_date());
+            println!("{}", t!("stats-header"));
+            println!("   {}", t!("stats-total", "count" => stats.total));
+            println!("   {}", t!("stats-active", "count" => stats.active));
+            println!("   {}", t!("stats-recalled", "count" => stats.recalled));
+            println!("   {}", t!("stats-soon-expiring", "count" => stats.soon_expiring));
+            println!("   {}", t!("stats-by-manufacturer-header"));
+            for (manufacturer, count) in &stats.by_manufacturer {
+                println!("     {}: {}", manufacturer, count);
+            }
+            if stats.duplicates.is_empty() {
+                println!("   {}", t!("stats-duplicates-none"));
+            } else {
+                println!("   {}", t!("stats-duplicates-header"));
+                for ((manufacturer, batch_number), ids) in &stats.duplicates {
+                    println!("     {} / {}: {} mints ({:?})", manufacturer, batch_number, ids.len(), ids);
+                }
             }
         },
         _ => {
-            println!("Unknown command. Use: mint, verify, or recall.");
+            println!("{}", t!("unknown-command"));
         }
     }
 }
+
+/// Extracts an optional `--lang <tag>` flag from the CLI args in place, so
+/// the remaining positional arguments keep their expected indices
+/// regardless of where the flag was passed.
+fn extract_lang_flag(args: &mut Vec<String>) -> Option<String> {
+    let i = args.iter().position(|a| a == "--lang")?;
+    let lang = args.get(i + 1).cloned();
+    if lang.is_some() {
+        args.remove(i + 1);
+    }
+    args.remove(i);
+    lang
+}
+
+/// Reads an optional trailing `--date-format <fmt>` flag off the CLI args,
+/// defaulting to `DateConversion::default()` when absent. `"rfc3339"` picks
+/// the RFC3339 variant; anything else is treated as a strftime pattern.
+fn parse_date_format_flag(args: &[String]) -> DateConversion {
+    match args.iter().position(|a| a == "--date-format").and_then(|i| args.get(i + 1)) {
+        None => DateConversion::default(),
+        Some(fmt) if fmt == "rfc3339" => DateConversion::Timestamp,
+        Some(fmt) => DateConversion::TimestampFmt(fmt.clone()),
+    }
+}
+
+fn now_unix_date() -> NaiveDate {
+    chrono::DateTime::from_timestamp(now_unix(), 0)
+        .expect("invalid system time")
+        .naive_utc()
+        .date()
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A recall list as published by a regulator, with replay protection.
+///
+/// `version` must never go backwards between snapshots a node accepts, and
+/// `expires` bounds how long a validly-signed snapshot can be replayed, so
+/// an attacker who captured an old signed snapshot can't feed it back to
+/// roll back or freeze a node's view of what's recalled.
+///
+/// `recalled` is a `BTreeSet` rather than a `HashSet` so it serializes to a
+/// deterministic byte order: a `HashSet`'s iteration order varies between
+/// instances of "the same" set, so signing one instance and verifying a
+/// deserialized copy would re-serialize in a different order and fail
+/// signature verification as soon as two or more batches are recalled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecallManifest {
+    pub recalled: BTreeSet<Uuid>,
+    pub version: u64,
+    pub expires: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedRecallManifest {
+    pub manifest: RecallManifest,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecallManifestError {
+    NoRegulatorKey,
+    BadSignature,
+    RolledBack,
+    Expired,
+}
+
+impl SignedRecallManifest {
+    pub fn sign(manifest: RecallManifest, regulator_key: &SigningKey) -> Self {
+        let bytes = serde_json::to_vec(&manifest).expect("manifest must serialize");
+        let signature = regulator_key.sign(&bytes).to_vec();
+        SignedRecallManifest { manifest, signature }
+    }
+
+    /// Validates the signature against the registered regulator key, then
+    /// rejects any snapshot that would roll the recall list back to an
+    /// older version or that has already expired.
+    pub fn verify(
+        &self,
+        regulator_key: Option<&VerifyingKey>,
+        last_seen_version: u64,
+        now: i64,
+    ) -> Result<(), RecallManifestError> {
+        let key = regulator_key.ok_or(RecallManifestError::NoRegulatorKey)?;
+        let signature = Signature::from_slice(&self.signature)
+            .map_err(|_| RecallManifestError::BadSignature)?;
+        let bytes = serde_json::to_vec(&self.manifest).expect("manifest must serialize");
+        key.verify(&bytes, &signature)
+            .map_err(|_| RecallManifestError::BadSignature)?;
+        if self.manifest.version < last_seen_version {
+            return Err(RecallManifestError::RolledBack);
+        }
+        if self.manifest.expires < now {
+            return Err(RecallManifestError::Expired);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signing a manifest that recalls two or more batches, then round-tripping
+    /// it through JSON (as `load-recall-manifest` does when reading the file a
+    /// regulator published) must still verify: `recalled` needs a deterministic
+    /// serialized order, or re-serializing the deserialized copy produces
+    /// different bytes than what was signed and every multi-batch manifest
+    /// fails `BadSignature`.
+    #[test]
+    fn verify_accepts_round_tripped_multi_batch_manifest() {
+        let regulator_key = SigningKey::generate(&mut rand_core::OsRng);
+        let manifest = RecallManifest {
+            recalled: BTreeSet::from([Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()]),
+            version: 1,
+            expires: i64::MAX,
+        };
+        let signed = SignedRecallManifest::sign(manifest, &regulator_key);
+
+        let bytes = serde_json::to_vec(&signed).expect("manifest must serialize");
+        let round_tripped: SignedRecallManifest =
+            serde_json::from_slice(&bytes).expect("manifest must deserialize");
+
+        assert_eq!(
+            round_tripped.verify(Some(&regulator_key.verifying_key()), 0, 0),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::dates::DateConversion;
+use crate::{crypto, DrugBatch, PharmaChain};
+
+type SharedChain = Arc<RwLock<PharmaChain>>;
+
+#[derive(Deserialize)]
+struct MintRequest {
+    name: String,
+    manufacturer: String,
+    batch_number: String,
+    /// Defaults to `%Y-%m-%d`; pass `date_format: "rfc3339"` for RFC3339 input.
+    expiry_date: String,
+    #[serde(default)]
+    date_format: Option<String>,
+    /// Hex-encoded raw manufacturer secret key bytes. This is a distinct
+    /// wire format from the PKCS#8 PEM files the `mint` CLI command reads
+    /// (`<secret_key.pem>`) — embedding a PEM block in JSON is awkward for
+    /// HTTP clients, so the API takes the raw 32 bytes as hex instead.
+    secret_key_hex: String,
+}
+
+#[derive(Serialize)]
+struct MintResponse {
+    id: Uuid,
+    qr_path: String,
+}
+
+#[derive(Serialize)]
+struct VerifyResponse {
+    batch: DrugBatch,
+    status: String,
+    qr_path: String,
+}
+
+#[derive(Deserialize)]
+struct RecallRequest {
+    /// Hex-encoded raw regulator secret key bytes (see `MintRequest::secret_key_hex`
+    /// for why this differs from the PEM files `pharmachain keygen` writes).
+    regulator_secret_key_hex: String,
+}
+
+#[derive(Serialize)]
+struct RecallsResponse {
+    recalled: Vec<Uuid>,
+}
+
+fn signing_key_from_hex(hex_str: &str) -> Result<ed25519_dalek::SigningKey, StatusCode> {
+    let bytes = hex::decode(hex_str.trim()).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(ed25519_dalek::SigningKey::from_bytes(&bytes))
+}
+
+async fn mint_batch(
+    State(chain): State<SharedChain>,
+    Json(req): Json<MintRequest>,
+) -> Result<(StatusCode, Json<MintResponse>), StatusCode> {
+    let signing_key = signing_key_from_hex(&req.secret_key_hex)?;
+    let date_format = match req.date_format.as_deref() {
+        None => DateConversion::default(),
+        Some("rfc3339") => DateConversion::Timestamp,
+        Some(fmt) => DateConversion::TimestampFmt(fmt.to_string()),
+    };
+    let mut chain = chain.write().await;
+    let id = chain
+        .mint_batch(&req.name, &req.manufacturer, &req.batch_number, &req.expiry_date, &date_format, &signing_key)
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(MintResponse { id, qr_path: format!("qr_{id}.png") }),
+    ))
+}
+
+async fn verify_batch(
+    State(chain): State<SharedChain>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerifyResponse>, StatusCode> {
+    let chain = chain.read().await;
+    let (batch, status) = chain.verify_batch(&id).ok_or(StatusCode::NOT_FOUND)?;
+    let status = match status {
+        crypto::VerifyStatus::Verified => "verified",
+        crypto::VerifyStatus::UnknownManufacturer => "unknown_manufacturer",
+        crypto::VerifyStatus::BadSignature => "bad_signature",
+    };
+    Ok(Json(VerifyResponse {
+        batch: batch.clone(),
+        status: status.to_string(),
+        qr_path: format!("qr_{id}.png"),
+    }))
+}
+
+async fn recall_batch(
+    State(chain): State<SharedChain>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RecallRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let regulator_key = signing_key_from_hex(&req.regulator_secret_key_hex)?;
+    let mut chain = chain.write().await;
+    if !chain.batches.contains_key(&id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if chain.is_recalled(&id) {
+        return Err(StatusCode::CONFLICT);
+    }
+    chain.recall_batch(&id, &regulator_key);
+    Ok(StatusCode::OK)
+}
+
+async fn list_recalls(State(chain): State<SharedChain>) -> Json<RecallsResponse> {
+    let chain = chain.read().await;
+    Json(RecallsResponse { recalled: chain.recalls.iter().copied().collect() })
+}
+
+fn router(chain: SharedChain) -> Router {
+    Router::new()
+        .route("/batches", post(mint_batch))
+        .route("/batches/:id", get(verify_batch))
+        .route("/batches/:id/recall", post(recall_batch))
+        .route("/recalls", get(list_recalls))
+        .with_state(chain)
+}
+
+/// Runs the HTTP API on `port`, serving requests against a single shared,
+/// lock-guarded `PharmaChain` until the process is killed.
+pub fn serve(chain: PharmaChain, port: u16) {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start async runtime");
+    runtime.block_on(async move {
+        let shared: SharedChain = Arc::new(RwLock::new(chain));
+        let app = router(shared);
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+            .await
+            .expect("failed to bind server port");
+        println!("🌐 PharmaChain API listening on http://0.0.0.0:{port}");
+        axum::serve(listener, app).await.expect("server error");
+    });
+}
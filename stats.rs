@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::PharmaChain;
+
+/// How many days out counts as "soon to expire" for the stats report.
+const SOON_EXPIRING_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug)]
+pub struct Stats {
+    pub total: usize,
+    pub recalled: usize,
+    pub active: usize,
+    pub soon_expiring: usize,
+    pub by_manufacturer: HashMap<String, usize>,
+    /// `(manufacturer, batch_number)` pairs minted under more than one
+    /// UUID — almost always an accidental re-mint of the same physical batch.
+    pub duplicates: HashMap<(String, String), Vec<Uuid>>,
+}
+
+pub fn compute(chain: &PharmaChain, as_of: NaiveDate) -> Stats {
+    let mut by_manufacturer: HashMap<String, usize> = HashMap::new();
+    let mut by_batch_key: HashMap<(String, String), Vec<Uuid>> = HashMap::new();
+    let mut recalled = 0;
+    let mut soon_expiring = 0;
+    let soon_cutoff = as_of + chrono::Duration::days(SOON_EXPIRING_WINDOW_DAYS);
+
+    for batch in chain.batches.values() {
+        *by_manufacturer.entry(batch.manufacturer.clone()).or_insert(0) += 1;
+        if batch.recalled {
+            recalled += 1;
+        } else if batch.expiry_date >= as_of && batch.expiry_date <= soon_cutoff {
+            soon_expiring += 1;
+        }
+        by_batch_key
+            .entry((batch.manufacturer.clone(), batch.batch_number.clone()))
+            .or_default()
+            .push(batch.id);
+    }
+
+    let duplicates = by_batch_key.into_iter().filter(|(_, ids)| ids.len() > 1).collect();
+
+    Stats {
+        total: chain.batches.len(),
+        recalled,
+        active: chain.batches.len() - recalled,
+        soon_expiring,
+        by_manufacturer,
+        duplicates,
+    }
+}